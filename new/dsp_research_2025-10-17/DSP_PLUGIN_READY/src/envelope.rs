@@ -0,0 +1,184 @@
+//! ADSR envelope generator
+//!
+//! Amplitude/parameter shaping for gated notes
+
+use crate::utils::clamp;
+
+/// Envelope stage
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stage {
+    Idle,
+    Attack,
+    Decay,
+    Sustain,
+    Release,
+}
+
+/// ADSR envelope generator
+pub struct Adsr {
+    sample_rate: f32,
+
+    attack_ms: f32,
+    decay_ms: f32,
+    sustain_level: f32,
+    release_ms: f32,
+
+    stage: Stage,
+    level: f32,
+}
+
+impl Adsr {
+    /// Create a new envelope at the given sample rate
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            sample_rate,
+            attack_ms: 10.0,
+            decay_ms: 100.0,
+            sustain_level: 0.7,
+            release_ms: 200.0,
+            stage: Stage::Idle,
+            level: 0.0,
+        }
+    }
+
+    /// Set attack time in milliseconds
+    #[inline]
+    pub fn set_attack(&mut self, ms: f32) {
+        self.attack_ms = clamp(ms, 0.0, 60_000.0);
+    }
+
+    /// Set decay time in milliseconds
+    #[inline]
+    pub fn set_decay(&mut self, ms: f32) {
+        self.decay_ms = clamp(ms, 0.0, 60_000.0);
+    }
+
+    /// Set sustain level (0.0 - 1.0)
+    #[inline]
+    pub fn set_sustain(&mut self, level: f32) {
+        self.sustain_level = clamp(level, 0.0, 1.0);
+    }
+
+    /// Set release time in milliseconds
+    #[inline]
+    pub fn set_release(&mut self, ms: f32) {
+        self.release_ms = clamp(ms, 0.0, 60_000.0);
+    }
+
+    /// Trigger a new note, starting the attack stage
+    pub fn trigger(&mut self) {
+        self.stage = Stage::Attack;
+    }
+
+    /// Release the current note, ramping to zero from the current level
+    pub fn release(&mut self) {
+        if self.stage != Stage::Idle {
+            self.stage = Stage::Release;
+        }
+    }
+
+    /// Per-sample increment for ramping from 0.0 to 1.0 over `time_ms`
+    fn increment(&self, time_ms: f32) -> f32 {
+        if time_ms <= 0.0 {
+            1.0
+        } else {
+            1.0 / (time_ms * 0.001 * self.sample_rate)
+        }
+    }
+
+    /// Generate the next envelope sample
+    pub fn next_sample(&mut self) -> f32 {
+        match self.stage {
+            Stage::Idle => {
+                self.level = 0.0;
+            }
+            Stage::Attack => {
+                self.level += self.increment(self.attack_ms);
+                if self.level >= 1.0 {
+                    self.level = 1.0;
+                    self.stage = Stage::Decay;
+                }
+            }
+            Stage::Decay => {
+                let step = self.increment(self.decay_ms) * (1.0 - self.sustain_level);
+                self.level -= step;
+                if self.level <= self.sustain_level {
+                    self.level = self.sustain_level;
+                    self.stage = Stage::Sustain;
+                }
+            }
+            Stage::Sustain => {
+                self.level = self.sustain_level;
+            }
+            Stage::Release => {
+                self.level -= self.increment(self.release_ms);
+                if self.level <= 0.0 {
+                    self.level = 0.0;
+                    self.stage = Stage::Idle;
+                }
+            }
+        }
+
+        self.level
+    }
+
+    /// Multiply a buffer in place by the envelope output
+    pub fn process(&mut self, buffer: &mut [f32]) {
+        for sample in buffer.iter_mut() {
+            *sample *= self.next_sample();
+        }
+    }
+
+    /// Whether the envelope is still producing non-zero output
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        self.stage != Stage::Idle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_idle_by_default() {
+        let mut env = Adsr::new(44100.0);
+        assert_eq!(env.next_sample(), 0.0);
+        assert!(!env.is_active());
+    }
+
+    #[test]
+    fn test_envelope_attack_reaches_peak() {
+        let mut env = Adsr::new(44100.0);
+        env.set_attack(1.0);
+        env.trigger();
+
+        let mut peak = 0.0f32;
+        for _ in 0..200 {
+            peak = peak.max(env.next_sample());
+        }
+
+        assert!((peak - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_envelope_release_returns_to_idle() {
+        let mut env = Adsr::new(44100.0);
+        env.set_attack(0.1);
+        env.set_decay(0.1);
+        env.set_sustain(0.5);
+        env.set_release(0.1);
+        env.trigger();
+
+        for _ in 0..100 {
+            env.next_sample();
+        }
+        env.release();
+        for _ in 0..1000 {
+            env.next_sample();
+        }
+
+        assert_eq!(env.next_sample(), 0.0);
+        assert!(!env.is_active());
+    }
+}
@@ -4,8 +4,9 @@
 //! DSP can be consumed from C or C++ audio plugin hosts.
 
 use crate::{
-    filter::Filter,
-    oscillator::{Oscillator, Waveform},
+    envelope::Adsr,
+    filter::{Filter, FilterMode},
+    oscillator::{Noise, NoiseMode, Oscillator, Waveform},
     utils,
 };
 use std::slice;
@@ -19,6 +20,25 @@ fn waveform_from_c(value: u32) -> Waveform {
     }
 }
 
+fn filter_mode_from_c(value: u32) -> FilterMode {
+    match value {
+        1 => FilterMode::Highpass,
+        2 => FilterMode::Bandpass,
+        3 => FilterMode::Notch,
+        4 => FilterMode::Peak,
+        5 => FilterMode::LowShelf,
+        6 => FilterMode::HighShelf,
+        _ => FilterMode::Lowpass,
+    }
+}
+
+fn noise_mode_from_c(value: u32) -> NoiseMode {
+    match value {
+        1 => NoiseMode::Pink,
+        _ => NoiseMode::White,
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Filter API
 // ---------------------------------------------------------------------------
@@ -63,6 +83,22 @@ pub unsafe extern "C" fn dsp_filter_set_smoothing(filter: *mut Filter, rate: f32
     }
 }
 
+/// Select the filter response type.
+#[no_mangle]
+pub unsafe extern "C" fn dsp_filter_set_mode(filter: *mut Filter, mode: u32) {
+    if let Some(filter) = unsafe { filter.as_mut() } {
+        filter.set_mode(filter_mode_from_c(mode));
+    }
+}
+
+/// Set the gain (in dB) used by the peaking and shelving modes.
+#[no_mangle]
+pub unsafe extern "C" fn dsp_filter_set_gain_db(filter: *mut Filter, gain_db: f32) {
+    if let Some(filter) = unsafe { filter.as_mut() } {
+        filter.set_gain_db(gain_db);
+    }
+}
+
 /// Process an audio buffer through the filter in-place.
 #[no_mangle]
 pub unsafe extern "C" fn dsp_filter_process(filter: *mut Filter, buffer: *mut f32, length: u32) {
@@ -147,6 +183,62 @@ pub unsafe extern "C" fn dsp_osc_reset(osc: *mut Oscillator) {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Noise API
+// ---------------------------------------------------------------------------
+
+/// Create a new noise generator for use via the C API.
+#[no_mangle]
+pub extern "C" fn dsp_noise_create(mode: u32, seed: u32) -> *mut Noise {
+    Box::into_raw(Box::new(Noise::new(noise_mode_from_c(mode), seed)))
+}
+
+/// Destroy a noise generator created with [`dsp_noise_create`].
+#[no_mangle]
+pub unsafe extern "C" fn dsp_noise_destroy(noise: *mut Noise) {
+    if !noise.is_null() {
+        unsafe {
+            drop(Box::from_raw(noise));
+        }
+    }
+}
+
+/// Select the noise mode.
+#[no_mangle]
+pub unsafe extern "C" fn dsp_noise_set_mode(noise: *mut Noise, mode: u32) {
+    if let Some(noise) = unsafe { noise.as_mut() } {
+        noise.set_mode(noise_mode_from_c(mode));
+    }
+}
+
+/// Set the noise amplitude.
+#[no_mangle]
+pub unsafe extern "C" fn dsp_noise_set_amplitude(noise: *mut Noise, amp: f32) {
+    if let Some(noise) = unsafe { noise.as_mut() } {
+        noise.set_amplitude(amp);
+    }
+}
+
+/// Generate noise into the supplied buffer.
+#[no_mangle]
+pub unsafe extern "C" fn dsp_noise_process(noise: *mut Noise, buffer: *mut f32, length: u32) {
+    if let Some(noise) = unsafe { noise.as_mut() } {
+        if buffer.is_null() || length == 0 {
+            return;
+        }
+        let buffer = unsafe { slice::from_raw_parts_mut(buffer, length as usize) };
+        noise.process(buffer);
+    }
+}
+
+/// Reset the noise generator's pink-filter state.
+#[no_mangle]
+pub unsafe extern "C" fn dsp_noise_reset(noise: *mut Noise) {
+    if let Some(noise) = unsafe { noise.as_mut() } {
+        noise.reset();
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Utility API
 // ---------------------------------------------------------------------------
@@ -174,3 +266,83 @@ pub extern "C" fn dsp_db_to_linear(db: f32) -> f32 {
 pub extern "C" fn dsp_linear_to_db(linear: f32) -> f32 {
     utils::linear_to_db(linear.max(1e-12))
 }
+
+// ---------------------------------------------------------------------------
+// Envelope API
+// ---------------------------------------------------------------------------
+
+/// Create a new ADSR envelope for use via the C API.
+#[no_mangle]
+pub extern "C" fn dsp_adsr_create(sample_rate: f32) -> *mut Adsr {
+    Box::into_raw(Box::new(Adsr::new(sample_rate)))
+}
+
+/// Destroy an envelope created with [`dsp_adsr_create`].
+#[no_mangle]
+pub unsafe extern "C" fn dsp_adsr_destroy(adsr: *mut Adsr) {
+    if !adsr.is_null() {
+        unsafe {
+            drop(Box::from_raw(adsr));
+        }
+    }
+}
+
+/// Configure the attack time in milliseconds.
+#[no_mangle]
+pub unsafe extern "C" fn dsp_adsr_set_attack(adsr: *mut Adsr, ms: f32) {
+    if let Some(adsr) = unsafe { adsr.as_mut() } {
+        adsr.set_attack(ms);
+    }
+}
+
+/// Configure the decay time in milliseconds.
+#[no_mangle]
+pub unsafe extern "C" fn dsp_adsr_set_decay(adsr: *mut Adsr, ms: f32) {
+    if let Some(adsr) = unsafe { adsr.as_mut() } {
+        adsr.set_decay(ms);
+    }
+}
+
+/// Configure the sustain level (0.0 - 1.0).
+#[no_mangle]
+pub unsafe extern "C" fn dsp_adsr_set_sustain(adsr: *mut Adsr, level: f32) {
+    if let Some(adsr) = unsafe { adsr.as_mut() } {
+        adsr.set_sustain(level);
+    }
+}
+
+/// Configure the release time in milliseconds.
+#[no_mangle]
+pub unsafe extern "C" fn dsp_adsr_set_release(adsr: *mut Adsr, ms: f32) {
+    if let Some(adsr) = unsafe { adsr.as_mut() } {
+        adsr.set_release(ms);
+    }
+}
+
+/// Trigger a new note, starting the attack stage.
+#[no_mangle]
+pub unsafe extern "C" fn dsp_adsr_trigger(adsr: *mut Adsr) {
+    if let Some(adsr) = unsafe { adsr.as_mut() } {
+        adsr.trigger();
+    }
+}
+
+/// Release the current note, ramping to zero.
+#[no_mangle]
+pub unsafe extern "C" fn dsp_adsr_release(adsr: *mut Adsr) {
+    if let Some(adsr) = unsafe { adsr.as_mut() } {
+        adsr.release();
+    }
+}
+
+/// Multiply an audio buffer in place by the envelope output.
+#[no_mangle]
+pub unsafe extern "C" fn dsp_adsr_process(adsr: *mut Adsr, buffer: *mut f32, length: u32) {
+    if let Some(adsr) = unsafe { adsr.as_mut() } {
+        if buffer.is_null() || length == 0 {
+            return;
+        }
+        let buffer = unsafe { slice::from_raw_parts_mut(buffer, length as usize) };
+        adsr.process(buffer);
+    }
+}
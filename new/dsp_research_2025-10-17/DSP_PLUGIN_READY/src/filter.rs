@@ -2,20 +2,37 @@
 //!
 //! Production-ready filter with smooth parameter changes
 
-use crate::utils::{clamp, smooth_param};
+use crate::utils::{clamp, db_to_linear, Tween, TweenMode};
 use std::f32::consts::PI;
 
+/// Biquad response type selected via [`Filter::set_mode`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterMode {
+    /// Low frequencies pass, high frequencies are attenuated
+    Lowpass,
+    /// High frequencies pass, low frequencies are attenuated
+    Highpass,
+    /// Only frequencies around the cutoff pass
+    Bandpass,
+    /// Frequencies around the cutoff are attenuated
+    Notch,
+    /// Boost or cut a band around the cutoff by `gain_db`
+    Peak,
+    /// Boost or cut frequencies below the cutoff by `gain_db`
+    LowShelf,
+    /// Boost or cut frequencies above the cutoff by `gain_db`
+    HighShelf,
+}
+
 /// EMU-style filter with smooth parameter changes
 pub struct Filter {
     sample_rate: f32,
 
-    // Current parameters
-    frequency: f32,
-    resonance: f32,
-
-    // Target parameters (for smoothing)
-    target_frequency: f32,
-    target_resonance: f32,
+    // Smoothed parameters
+    freq_tween: Tween,
+    res_tween: Tween,
+    mode: FilterMode,
+    gain_db: f32,
 
     // Biquad coefficients
     b0: f32,
@@ -29,20 +46,22 @@ pub struct Filter {
     x2: f32,
     y1: f32,
     y2: f32,
-
-    // Smoothing rate
-    smoothing: f32,
 }
 
 impl Filter {
     /// Create new filter at given sample rate
     pub fn new(sample_rate: f32) -> Self {
+        let freq_tween = Tween::new(1000.0, TweenMode::Exponential { rate: 0.001 })
+            .with_bounds(20.0, sample_rate * 0.45);
+        let res_tween =
+            Tween::new(0.5, TweenMode::Exponential { rate: 0.001 }).with_bounds(0.0, 0.99);
+
         let mut filter = Self {
             sample_rate,
-            frequency: 1000.0,
-            resonance: 0.5,
-            target_frequency: 1000.0,
-            target_resonance: 0.5,
+            freq_tween,
+            res_tween,
+            mode: FilterMode::Lowpass,
+            gain_db: 0.0,
             b0: 0.0,
             b1: 0.0,
             b2: 0.0,
@@ -52,7 +71,6 @@ impl Filter {
             x2: 0.0,
             y1: 0.0,
             y2: 0.0,
-            smoothing: 0.001,
         };
         filter.update_coefficients();
         filter
@@ -61,29 +79,50 @@ impl Filter {
     /// Set cutoff frequency (20Hz - 20kHz)
     #[inline]
     pub fn set_frequency(&mut self, freq: f32) {
-        self.target_frequency = clamp(freq, 20.0, self.sample_rate * 0.45);
+        self.freq_tween.set_target(freq);
     }
 
     /// Set resonance (0.0 - 1.0)
     #[inline]
     pub fn set_resonance(&mut self, res: f32) {
-        self.target_resonance = clamp(res, 0.0, 0.99);
+        self.res_tween.set_target(res);
     }
 
     /// Set smoothing rate (0.0 = instant, 1.0 = very slow)
     #[inline]
     pub fn set_smoothing(&mut self, rate: f32) {
-        self.smoothing = clamp(rate, 0.0, 0.1);
+        let mode = TweenMode::Exponential {
+            rate: 1.0 - clamp(rate, 0.0, 0.999),
+        };
+        self.freq_tween.set_mode(mode);
+        self.res_tween.set_mode(mode);
+    }
+
+    /// Select the filter response type
+    #[inline]
+    pub fn set_mode(&mut self, mode: FilterMode) {
+        self.mode = mode;
+        self.update_coefficients();
+    }
+
+    /// Set the gain in dB used by the `Peak`, `LowShelf` and `HighShelf` modes
+    #[inline]
+    pub fn set_gain_db(&mut self, gain_db: f32) {
+        self.gain_db = gain_db;
+        self.update_coefficients();
     }
 
     /// Process audio buffer in-place
     pub fn process(&mut self, buffer: &mut [f32]) {
+        // Update coefficients if either parameter hadn't settled before this
+        // tick, including the tick on which it lands on its target
+        let was_settled = self.freq_tween.is_settled() && self.res_tween.is_settled();
+
         // Smooth parameters
-        self.frequency = smooth_param(self.frequency, self.target_frequency, self.smoothing);
-        self.resonance = smooth_param(self.resonance, self.target_resonance, self.smoothing);
+        self.freq_tween.tick();
+        self.res_tween.tick();
 
-        // Update coefficients if needed
-        if (self.frequency - self.target_frequency).abs() > 1.0 {
+        if !was_settled {
             self.update_coefficients();
         }
 
@@ -112,22 +151,90 @@ impl Filter {
 
     /// Update biquad coefficients
     fn update_coefficients(&mut self) {
-        let omega = 2.0 * PI * self.frequency / self.sample_rate;
+        let omega = 2.0 * PI * self.freq_tween.current() / self.sample_rate;
         let sin_omega = omega.sin();
         let cos_omega = omega.cos();
 
         // Q factor from resonance (logarithmic mapping)
-        let q = 0.5 + self.resonance * 10.0;
+        let q = 0.5 + self.res_tween.current() * 10.0;
         let alpha = sin_omega / (2.0 * q);
 
-        // Lowpass coefficients
-        let a0 = 1.0 + alpha;
+        let a0;
+        let (b0, b1, b2, a1, a2);
+
+        match self.mode {
+            FilterMode::Lowpass => {
+                a0 = 1.0 + alpha;
+                b0 = (1.0 - cos_omega) / 2.0;
+                b1 = 1.0 - cos_omega;
+                b2 = (1.0 - cos_omega) / 2.0;
+                a1 = -2.0 * cos_omega;
+                a2 = 1.0 - alpha;
+            }
+            FilterMode::Highpass => {
+                a0 = 1.0 + alpha;
+                b0 = (1.0 + cos_omega) / 2.0;
+                b1 = -(1.0 + cos_omega);
+                b2 = (1.0 + cos_omega) / 2.0;
+                a1 = -2.0 * cos_omega;
+                a2 = 1.0 - alpha;
+            }
+            FilterMode::Bandpass => {
+                a0 = 1.0 + alpha;
+                b0 = alpha;
+                b1 = 0.0;
+                b2 = -alpha;
+                a1 = -2.0 * cos_omega;
+                a2 = 1.0 - alpha;
+            }
+            FilterMode::Notch => {
+                a0 = 1.0 + alpha;
+                b0 = 1.0;
+                b1 = -2.0 * cos_omega;
+                b2 = 1.0;
+                a1 = -2.0 * cos_omega;
+                a2 = 1.0 - alpha;
+            }
+            FilterMode::Peak => {
+                // RBJ cookbook's `A` term is the *square root* of the linear
+                // gain ratio (10^(dBgain/40)), not db_to_linear's 10^(dBgain/20).
+                let a = db_to_linear(self.gain_db * 0.5);
+                a0 = 1.0 + alpha / a;
+                b0 = 1.0 + alpha * a;
+                b1 = -2.0 * cos_omega;
+                b2 = 1.0 - alpha * a;
+                a1 = -2.0 * cos_omega;
+                a2 = 1.0 - alpha / a;
+            }
+            FilterMode::LowShelf => {
+                let gain = db_to_linear(self.gain_db * 0.5);
+                let sqrt_gain = gain.sqrt();
+                let two_sqrt_gain_alpha = 2.0 * sqrt_gain * alpha;
+                a0 = (gain + 1.0) + (gain - 1.0) * cos_omega + two_sqrt_gain_alpha;
+                b0 = gain * ((gain + 1.0) - (gain - 1.0) * cos_omega + two_sqrt_gain_alpha);
+                b1 = 2.0 * gain * ((gain - 1.0) - (gain + 1.0) * cos_omega);
+                b2 = gain * ((gain + 1.0) - (gain - 1.0) * cos_omega - two_sqrt_gain_alpha);
+                a1 = -2.0 * ((gain - 1.0) + (gain + 1.0) * cos_omega);
+                a2 = (gain + 1.0) + (gain - 1.0) * cos_omega - two_sqrt_gain_alpha;
+            }
+            FilterMode::HighShelf => {
+                let gain = db_to_linear(self.gain_db * 0.5);
+                let sqrt_gain = gain.sqrt();
+                let two_sqrt_gain_alpha = 2.0 * sqrt_gain * alpha;
+                a0 = (gain + 1.0) - (gain - 1.0) * cos_omega + two_sqrt_gain_alpha;
+                b0 = gain * ((gain + 1.0) + (gain - 1.0) * cos_omega + two_sqrt_gain_alpha);
+                b1 = -2.0 * gain * ((gain - 1.0) + (gain + 1.0) * cos_omega);
+                b2 = gain * ((gain + 1.0) + (gain - 1.0) * cos_omega - two_sqrt_gain_alpha);
+                a1 = 2.0 * ((gain - 1.0) - (gain + 1.0) * cos_omega);
+                a2 = (gain + 1.0) - (gain - 1.0) * cos_omega - two_sqrt_gain_alpha;
+            }
+        }
 
-        self.b0 = (1.0 - cos_omega) / (2.0 * a0);
-        self.b1 = (1.0 - cos_omega) / a0;
-        self.b2 = (1.0 - cos_omega) / (2.0 * a0);
-        self.a1 = -2.0 * cos_omega / a0;
-        self.a2 = (1.0 - alpha) / a0;
+        self.b0 = b0 / a0;
+        self.b1 = b1 / a0;
+        self.b2 = b2 / a0;
+        self.a1 = a1 / a0;
+        self.a2 = a2 / a0;
     }
 
     /// Reset filter state
@@ -140,18 +247,78 @@ impl Filter {
 
     /// Get current frequency
     pub fn frequency(&self) -> f32 {
-        self.frequency
+        self.freq_tween.current()
     }
 
     /// Get current resonance
     pub fn resonance(&self) -> f32 {
-        self.resonance
+        self.res_tween.current()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::linear_to_db;
+
+    /// Magnitude response of `filter`'s current biquad coefficients at
+    /// `freq`, in dB, evaluated directly from `H(e^{jw})` rather than by
+    /// processing audio (coefficients are already settled for a freshly
+    /// constructed filter, since the frequency/resonance tweens start at
+    /// their target values).
+    fn magnitude_db(filter: &Filter, freq: f32) -> f32 {
+        let omega = 2.0 * PI * freq / filter.sample_rate;
+        let (sin1, cos1) = (omega.sin(), omega.cos());
+        let (sin2, cos2) = ((2.0 * omega).sin(), (2.0 * omega).cos());
+
+        let num_re = filter.b0 + filter.b1 * cos1 + filter.b2 * cos2;
+        let num_im = -filter.b1 * sin1 - filter.b2 * sin2;
+        let den_re = 1.0 + filter.a1 * cos1 + filter.a2 * cos2;
+        let den_im = -filter.a1 * sin1 - filter.a2 * sin2;
+
+        let num_mag = (num_re * num_re + num_im * num_im).sqrt();
+        let den_mag = (den_re * den_re + den_im * den_im).sqrt();
+
+        linear_to_db(num_mag / den_mag)
+    }
+
+    #[test]
+    fn test_peak_and_shelf_gain_matches_requested_db() {
+        let sample_rate = 44100.0;
+
+        let mut peak = Filter::new(sample_rate);
+        peak.set_mode(FilterMode::Peak);
+        peak.set_frequency(1000.0);
+        peak.set_resonance(0.5);
+        peak.set_gain_db(6.0);
+        assert!((magnitude_db(&peak, 1000.0) - 6.0).abs() < 0.1);
+
+        let mut high_shelf = Filter::new(sample_rate);
+        high_shelf.set_mode(FilterMode::HighShelf);
+        high_shelf.set_frequency(1000.0);
+        high_shelf.set_resonance(0.5);
+        high_shelf.set_gain_db(6.0);
+        assert!((magnitude_db(&high_shelf, 15000.0) - 6.0).abs() < 0.2);
+
+        let mut low_shelf = Filter::new(sample_rate);
+        low_shelf.set_mode(FilterMode::LowShelf);
+        low_shelf.set_frequency(1000.0);
+        low_shelf.set_resonance(0.5);
+        low_shelf.set_gain_db(6.0);
+        assert!((magnitude_db(&low_shelf, 20.0) - 6.0).abs() < 0.2);
+    }
+
+    #[test]
+    fn test_smoothing_zero_is_instant() {
+        let mut filter = Filter::new(44100.0);
+        filter.set_smoothing(0.0);
+        filter.set_frequency(5000.0);
+
+        // A rate of 0.0 ("instant" per the doc) should reach the target on
+        // the very next tick, not freeze at the old value forever.
+        filter.process(&mut [0.0f32]);
+        assert_eq!(filter.frequency(), 5000.0);
+    }
 
     #[test]
     fn test_filter_creation() {
@@ -173,4 +340,31 @@ mod tests {
         // Should have filtered the impulse
         assert!(buffer.iter().any(|&x| x != 0.0));
     }
+
+    #[test]
+    fn test_filter_modes_produce_output() {
+        let modes = [
+            FilterMode::Lowpass,
+            FilterMode::Highpass,
+            FilterMode::Bandpass,
+            FilterMode::Notch,
+            FilterMode::Peak,
+            FilterMode::LowShelf,
+            FilterMode::HighShelf,
+        ];
+
+        for mode in modes {
+            let mut filter = Filter::new(44100.0);
+            filter.set_mode(mode);
+            filter.set_gain_db(6.0);
+            filter.set_frequency(1000.0);
+            filter.set_resonance(0.5);
+
+            let mut buffer = vec![0.0f32; 64];
+            buffer[0] = 1.0;
+            filter.process(&mut buffer);
+
+            assert!(buffer.iter().any(|&x| x != 0.0));
+        }
+    }
 }
@@ -6,14 +6,24 @@
 #![deny(unsafe_op_in_unsafe_fn)]
 #![warn(missing_docs)]
 
+pub mod envelope;
 pub mod filter;
 pub mod oscillator;
+pub mod sequencer;
+pub mod source;
 pub mod utils;
 
 // Re-export main types
+pub use envelope::Adsr;
 pub use filter::Filter;
+pub use filter::FilterMode;
+pub use oscillator::Noise;
+pub use oscillator::NoiseMode;
 pub use oscillator::Oscillator;
 pub use oscillator::Waveform;
+pub use sequencer::{Instrument, Pattern, Row, Song};
+pub use source::SampleSource;
+pub use utils::{Tween, TweenMode};
 
 /// Library version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
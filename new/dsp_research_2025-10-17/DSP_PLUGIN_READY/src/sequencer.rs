@@ -0,0 +1,332 @@
+//! Pattern/sequencer subsystem for offline song rendering
+//!
+//! Lets a song be authored as instruments, note patterns and a per-instrument
+//! sequence of pattern indices, instead of hand-rolling note loops.
+
+use crate::envelope::Adsr;
+use crate::filter::Filter;
+use crate::oscillator::{Oscillator, Waveform};
+use crate::utils::midi_to_freq;
+
+/// A single oscillator/envelope/filter voice
+pub struct Instrument {
+    oscillator: Oscillator,
+    envelope: Adsr,
+    filter: Option<Filter>,
+}
+
+impl Instrument {
+    /// Create a new instrument at the given sample rate
+    pub fn new(sample_rate: f32, waveform: Waveform) -> Self {
+        let mut oscillator = Oscillator::new(sample_rate);
+        oscillator.set_waveform(waveform);
+
+        Self {
+            oscillator,
+            envelope: Adsr::new(sample_rate),
+            filter: None,
+        }
+    }
+
+    /// Attach a filter that every rendered sample passes through
+    pub fn set_filter(&mut self, filter: Filter) {
+        self.filter = Some(filter);
+    }
+
+    /// Trigger a note at the given MIDI note number
+    fn note_on(&mut self, note: u8) {
+        self.oscillator.set_frequency(midi_to_freq(note as f32));
+        self.oscillator.reset();
+        self.envelope.trigger();
+    }
+
+    fn next_sample(&mut self) -> f32 {
+        let mut sample = [0.0f32];
+        self.oscillator.process(&mut sample);
+        self.envelope.process(&mut sample);
+        if let Some(filter) = &mut self.filter {
+            filter.process(&mut sample);
+        }
+        sample[0]
+    }
+}
+
+/// A single step in a [`Pattern`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Row {
+    /// MIDI note number to trigger on this row, or `None` to hold/rest
+    pub note: Option<u8>,
+    /// Note velocity (0.0 - 1.0)
+    pub velocity: f32,
+}
+
+impl Row {
+    /// A row that triggers `note` at full velocity
+    pub fn note(note: u8) -> Self {
+        Self {
+            note: Some(note),
+            velocity: 1.0,
+        }
+    }
+
+    /// A row with no note (silence/hold)
+    pub fn rest() -> Self {
+        Self {
+            note: None,
+            velocity: 0.0,
+        }
+    }
+}
+
+/// A sequence of note rows, one quarter note apart
+#[derive(Debug, Clone, Default)]
+pub struct Pattern {
+    /// The rows making up this pattern
+    pub rows: Vec<Row>,
+}
+
+impl Pattern {
+    /// Create a pattern from a list of rows
+    pub fn new(rows: Vec<Row>) -> Self {
+        Self { rows }
+    }
+}
+
+/// A full song: instruments, the patterns they can play, and the order each
+/// instrument plays them in
+pub struct Song {
+    instruments: Vec<Instrument>,
+    patterns: Vec<Pattern>,
+    /// Per-instrument sequence of indices into `patterns`
+    sequences: Vec<Vec<usize>>,
+    quarter_note_samples: usize,
+}
+
+impl Song {
+    /// Create a new, empty song
+    pub fn new(quarter_note_samples: usize) -> Self {
+        Self {
+            instruments: Vec::new(),
+            patterns: Vec::new(),
+            sequences: Vec::new(),
+            quarter_note_samples,
+        }
+    }
+
+    /// Add an instrument along with its pattern sequence, returning its index
+    pub fn add_instrument(&mut self, instrument: Instrument, sequence: Vec<usize>) -> usize {
+        self.instruments.push(instrument);
+        self.sequences.push(sequence);
+        self.instruments.len() - 1
+    }
+
+    /// Add a pattern to the song's pattern pool, returning its index
+    pub fn add_pattern(&mut self, pattern: Pattern) -> usize {
+        self.patterns.push(pattern);
+        self.patterns.len() - 1
+    }
+
+    /// Render the whole song to a buffer, summing every instrument's output.
+    ///
+    /// Timing comes entirely from `quarter_note_samples` (set in [`Song::new`])
+    /// and each [`Instrument`]'s own sample rate, both of which the caller
+    /// must already have derived from the rate they want to render at.
+    pub fn render(&mut self) -> Vec<f32> {
+        let total_samples = self.total_samples();
+        let mut mix = vec![0.0f32; total_samples];
+
+        for instrument_index in 0..self.instruments.len() {
+            self.render_instrument(instrument_index, &mut mix);
+        }
+
+        mix
+    }
+
+    /// Render a song incrementally, yielding one mixed sample at a time
+    pub fn render_streaming(self) -> SongStream {
+        SongStream::new(self)
+    }
+
+    fn total_samples(&self) -> usize {
+        self.sequences
+            .iter()
+            .map(|sequence| {
+                sequence
+                    .iter()
+                    .map(|&pattern_index| self.patterns[pattern_index].rows.len())
+                    .sum::<usize>()
+                    * self.quarter_note_samples
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn render_instrument(&mut self, instrument_index: usize, mix: &mut [f32]) {
+        let sequence = self.sequences[instrument_index].clone();
+        let quarter_note_samples = self.quarter_note_samples;
+        let instrument = &mut self.instruments[instrument_index];
+
+        let mut position = 0;
+        for pattern_index in sequence {
+            for row in &self.patterns[pattern_index].rows.clone() {
+                if let Some(note) = row.note {
+                    instrument.note_on(note);
+                }
+
+                for i in 0..quarter_note_samples {
+                    if position + i >= mix.len() {
+                        break;
+                    }
+                    mix[position + i] += instrument.next_sample() * row.velocity;
+                }
+
+                position += quarter_note_samples;
+            }
+        }
+    }
+}
+
+/// Incrementally pulls mixed samples from a [`Song`] without pre-rendering
+/// the whole buffer up front
+pub struct SongStream {
+    song: Song,
+    cursors: Vec<SequenceCursor>,
+}
+
+struct SequenceCursor {
+    sequence_index: usize,
+    row_in_pattern: usize,
+    sample_in_row: usize,
+    triggered: bool,
+}
+
+impl SongStream {
+    fn new(song: Song) -> Self {
+        let cursors = (0..song.instruments.len())
+            .map(|_| SequenceCursor {
+                sequence_index: 0,
+                row_in_pattern: 0,
+                sample_in_row: 0,
+                triggered: false,
+            })
+            .collect();
+
+        Self { song, cursors }
+    }
+}
+
+impl Iterator for SongStream {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let quarter_note_samples = self.song.quarter_note_samples;
+        let mut mixed = 0.0f32;
+        let mut any_active = false;
+
+        for (instrument_index, cursor) in self.cursors.iter_mut().enumerate() {
+            let sequence = &self.song.sequences[instrument_index];
+
+            // Skip empty patterns (a valid `Pattern::new(vec![])` rest
+            // placeholder) entirely rather than indexing into their empty
+            // `rows`.
+            while cursor.sequence_index < sequence.len()
+                && self.song.patterns[sequence[cursor.sequence_index]]
+                    .rows
+                    .is_empty()
+            {
+                cursor.sequence_index += 1;
+                cursor.row_in_pattern = 0;
+                cursor.sample_in_row = 0;
+                cursor.triggered = false;
+            }
+
+            if cursor.sequence_index >= sequence.len() {
+                continue;
+            }
+            any_active = true;
+
+            let pattern_index = sequence[cursor.sequence_index];
+            let pattern = &self.song.patterns[pattern_index];
+            let row = pattern.rows[cursor.row_in_pattern];
+
+            if !cursor.triggered {
+                if let Some(note) = row.note {
+                    self.song.instruments[instrument_index].note_on(note);
+                }
+                cursor.triggered = true;
+            }
+
+            mixed += self.song.instruments[instrument_index].next_sample() * row.velocity;
+
+            cursor.sample_in_row += 1;
+            if cursor.sample_in_row >= quarter_note_samples {
+                cursor.sample_in_row = 0;
+                cursor.triggered = false;
+                cursor.row_in_pattern += 1;
+                if cursor.row_in_pattern >= pattern.rows.len() {
+                    cursor.row_in_pattern = 0;
+                    cursor.sequence_index += 1;
+                }
+            }
+        }
+
+        if any_active {
+            Some(mixed)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_song_render_produces_audio() {
+        let sample_rate = 44100.0;
+        let quarter_note_samples = (sample_rate * 0.25) as usize;
+        let mut song = Song::new(quarter_note_samples);
+
+        let pattern = Pattern::new(vec![Row::note(60), Row::note(64), Row::rest()]);
+        let pattern_index = song.add_pattern(pattern);
+
+        let instrument = Instrument::new(sample_rate, Waveform::Sine);
+        song.add_instrument(instrument, vec![pattern_index]);
+
+        let audio = song.render();
+
+        assert_eq!(audio.len(), quarter_note_samples * 3);
+        assert!(audio.iter().any(|&x| x != 0.0));
+    }
+
+    #[test]
+    fn test_song_streaming_matches_render_length() {
+        let sample_rate = 44100.0;
+        let quarter_note_samples = 128;
+        let mut song = Song::new(quarter_note_samples);
+
+        let pattern = Pattern::new(vec![Row::note(60), Row::rest()]);
+        let pattern_index = song.add_pattern(pattern);
+        let instrument = Instrument::new(sample_rate, Waveform::Sine);
+        song.add_instrument(instrument, vec![pattern_index]);
+
+        let streamed: Vec<f32> = song.render_streaming().collect();
+        assert_eq!(streamed.len(), quarter_note_samples * 2);
+    }
+
+    #[test]
+    fn test_streaming_skips_empty_pattern_without_panicking() {
+        let sample_rate = 44100.0;
+        let quarter_note_samples = 128;
+        let mut song = Song::new(quarter_note_samples);
+
+        let empty_pattern = song.add_pattern(Pattern::new(vec![]));
+        let note_pattern = song.add_pattern(Pattern::new(vec![Row::note(60)]));
+        let instrument = Instrument::new(sample_rate, Waveform::Sine);
+        song.add_instrument(instrument, vec![empty_pattern, note_pattern]);
+
+        let streamed: Vec<f32> = song.render_streaming().collect();
+        assert_eq!(streamed.len(), quarter_note_samples);
+    }
+}
@@ -0,0 +1,74 @@
+//! Pull-based streaming source API
+//!
+//! Lets oscillators, noise generators and filters be composed as lazy
+//! iterators instead of pre-allocating buffers for every stage of a graph.
+//! Envelopes ([`Adsr`](crate::envelope::Adsr)) do not implement
+//! [`SampleSource`] and cannot be chained here; they are applied to a buffer
+//! with `Adsr::process`.
+
+use crate::filter::Filter;
+
+/// A DSP component that can be pulled one sample at a time
+pub trait SampleSource {
+    /// Produce the next sample
+    fn next_sample(&mut self) -> f32;
+}
+
+/// Wraps a [`Filter`] and an upstream [`SampleSource`], filtering samples lazily
+pub struct FilteredSource<S: SampleSource> {
+    filter: Filter,
+    source: S,
+}
+
+impl<S: SampleSource> FilteredSource<S> {
+    /// Create a new filtered source
+    pub fn new(filter: Filter, source: S) -> Self {
+        Self { filter, source }
+    }
+
+    /// Borrow the inner filter to adjust its parameters
+    pub fn filter_mut(&mut self) -> &mut Filter {
+        &mut self.filter
+    }
+}
+
+impl<S: SampleSource> SampleSource for FilteredSource<S> {
+    fn next_sample(&mut self) -> f32 {
+        let mut sample = [self.source.next_sample()];
+        self.filter.process(&mut sample);
+        sample[0]
+    }
+}
+
+impl<S: SampleSource> Iterator for FilteredSource<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        Some(self.next_sample())
+    }
+}
+
+impl Filter {
+    /// Wrap any [`SampleSource`] so pulling from the result yields filtered samples lazily
+    pub fn wrap<S: SampleSource>(self, source: S) -> FilteredSource<S> {
+        FilteredSource::new(self, source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oscillator::Oscillator;
+
+    #[test]
+    fn test_filter_wraps_oscillator_lazily() {
+        let mut osc = Oscillator::new(44100.0);
+        osc.set_frequency(440.0);
+
+        let filter = Filter::new(44100.0);
+        let chained = filter.wrap(osc);
+
+        let samples: Vec<f32> = chained.take(16).collect();
+        assert_eq!(samples.len(), 16);
+    }
+}
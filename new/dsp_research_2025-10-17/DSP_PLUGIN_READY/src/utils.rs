@@ -1,5 +1,47 @@
 //! DSP utility functions
 
+use std::f32::consts::PI;
+use std::sync::OnceLock;
+
+/// Number of entries in the cosine lookup table (power of two)
+const COS_TABLE_SIZE: usize = 512;
+
+/// `COS_TABLE_SIZE + 1` entries so the final entry mirrors the first, keeping
+/// interpolation near the wraparound artifact-free.
+static COS_TABLE: OnceLock<[f32; COS_TABLE_SIZE + 1]> = OnceLock::new();
+
+fn cos_table() -> &'static [f32; COS_TABLE_SIZE + 1] {
+    COS_TABLE.get_or_init(|| {
+        let mut table = [0.0f32; COS_TABLE_SIZE + 1];
+        for (i, entry) in table.iter_mut().enumerate() {
+            *entry = ((i as f32) / COS_TABLE_SIZE as f32 * 2.0 * PI).cos();
+        }
+        table
+    })
+}
+
+/// Fast cosine approximation backed by a 512-entry lookup table with linear
+/// interpolation. Accurate to better than 0.001 for typical oscillator and
+/// filter-coefficient use.
+#[inline]
+pub fn fast_cos(x: f32) -> f32 {
+    let table = cos_table();
+
+    // Cosine is even, so fold negative inputs onto the positive side
+    let normalized = (x.abs() / (2.0 * PI)).fract();
+    let index_f = normalized * COS_TABLE_SIZE as f32;
+    let index = index_f as usize;
+    let frac = index_f - index as f32;
+
+    lerp(table[index], table[index + 1], frac)
+}
+
+/// Fast sine approximation, derived from [`fast_cos`] via the `sin(x) = cos(x - PI/2)` identity.
+#[inline]
+pub fn fast_sin(x: f32) -> f32 {
+    fast_cos(x - PI / 2.0)
+}
+
 /// Clamp value between min and max
 #[inline]
 pub fn clamp(value: f32, min: f32, max: f32) -> f32 {
@@ -36,6 +78,113 @@ pub fn linear_to_db(linear: f32) -> f32 {
     20.0 * linear.log10()
 }
 
+/// Convert a MIDI note number to a frequency in Hz (A4 = note 69 = 440 Hz)
+#[inline]
+pub fn midi_to_freq(note: f32) -> f32 {
+    440.0 * 2.0_f32.powf((note - 69.0) / 12.0)
+}
+
+/// How a [`Tween`] interpolates toward its target
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TweenMode {
+    /// Move a fixed fraction of the remaining distance each tick (one-pole)
+    Exponential {
+        /// 0.0 = never moves, 1.0 = snaps instantly
+        rate: f32,
+    },
+    /// Move a fixed amount each tick so the target is reached in exactly `samples` ticks
+    Linear {
+        /// Number of ticks over which to reach the target
+        samples: u32,
+    },
+}
+
+/// General-purpose smoothed parameter, replacing ad-hoc one-off smoothing
+/// like [`smooth_param`]. Supports either exponential one-pole smoothing or a
+/// linear ramp over a fixed number of samples, with optional clamping.
+pub struct Tween {
+    current: f32,
+    target: f32,
+    mode: TweenMode,
+    step: f32,
+    min: f32,
+    max: f32,
+}
+
+impl Tween {
+    /// Create a tween starting at `initial`, smoothing via `mode`
+    pub fn new(initial: f32, mode: TweenMode) -> Self {
+        Self {
+            current: initial,
+            target: initial,
+            mode,
+            step: 0.0,
+            min: f32::MIN,
+            max: f32::MAX,
+        }
+    }
+
+    /// Clamp all future output to `[min, max]`
+    pub fn with_bounds(mut self, min: f32, max: f32) -> Self {
+        self.min = min;
+        self.max = max;
+        self.current = clamp(self.current, min, max);
+        self
+    }
+
+    /// Change the smoothing mode
+    pub fn set_mode(&mut self, mode: TweenMode) {
+        self.mode = mode;
+    }
+
+    /// Set a new target value to ramp toward
+    pub fn set_target(&mut self, target: f32) {
+        self.target = clamp(target, self.min, self.max);
+        if let TweenMode::Linear { samples } = self.mode {
+            let samples = samples.max(1) as f32;
+            self.step = (self.target - self.current) / samples;
+        }
+    }
+
+    /// Current value without advancing
+    #[inline]
+    pub fn current(&self) -> f32 {
+        self.current
+    }
+
+    /// Target value
+    #[inline]
+    pub fn target(&self) -> f32 {
+        self.target
+    }
+
+    /// Whether the tween has reached its target
+    #[inline]
+    pub fn is_settled(&self) -> bool {
+        self.current == self.target
+    }
+
+    /// Advance one sample toward the target, returning the new current value
+    pub fn tick(&mut self) -> f32 {
+        match self.mode {
+            TweenMode::Exponential { rate } => {
+                self.current = smooth_param(self.current, self.target, rate);
+            }
+            TweenMode::Linear { .. } => {
+                self.current += self.step;
+                let overshot = (self.step >= 0.0 && self.current >= self.target)
+                    || (self.step <= 0.0 && self.current <= self.target);
+                if overshot {
+                    self.current = self.target;
+                }
+            }
+        }
+
+        self.current = clamp(self.current, self.min, self.max);
+        self.current
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,4 +211,53 @@ mod tests {
         let db = linear_to_db(1.0);
         assert!(db.abs() < 0.001);
     }
+
+    #[test]
+    fn test_midi_to_freq() {
+        assert!((midi_to_freq(69.0) - 440.0).abs() < 0.001);
+        assert!((midi_to_freq(57.0) - 220.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_tween_exponential_approaches_target() {
+        let mut tween = Tween::new(0.0, TweenMode::Exponential { rate: 0.5 });
+        tween.set_target(1.0);
+
+        for _ in 0..50 {
+            tween.tick();
+        }
+
+        assert!((tween.current() - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_tween_linear_reaches_target_exactly() {
+        let mut tween = Tween::new(0.0, TweenMode::Linear { samples: 10 });
+        tween.set_target(10.0);
+
+        for _ in 0..10 {
+            tween.tick();
+        }
+
+        assert_eq!(tween.current(), 10.0);
+        assert!(tween.is_settled());
+    }
+
+    #[test]
+    fn test_tween_respects_bounds() {
+        let mut tween = Tween::new(0.0, TweenMode::Linear { samples: 1 }).with_bounds(0.0, 5.0);
+        tween.set_target(100.0);
+        tween.tick();
+
+        assert_eq!(tween.current(), 5.0);
+    }
+
+    #[test]
+    fn test_fast_trig_matches_std() {
+        for i in 0..1000 {
+            let x = (i as f32 / 1000.0) * 4.0 * PI - 2.0 * PI;
+            assert!((fast_sin(x) - x.sin()).abs() < 0.001);
+            assert!((fast_cos(x) - x.cos()).abs() < 0.001);
+        }
+    }
 }
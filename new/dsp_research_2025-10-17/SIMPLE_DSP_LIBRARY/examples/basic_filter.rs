@@ -9,7 +9,7 @@ fn main() {
     // Set filter parameters
     filter.set_frequency(1000.0); // 1kHz cutoff
     filter.set_resonance(0.7); // Some resonance
-    filter.set_smoothing(0.01); // Smooth parameter changes
+    filter.set_smoothing_ms(5.0); // Smooth parameter changes
 
     // Generate some test audio (white noise)
     let mut audio = vec![0.0f32; 1024];
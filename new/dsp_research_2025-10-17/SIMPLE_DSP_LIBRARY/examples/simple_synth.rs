@@ -21,7 +21,7 @@ fn main() {
     let mut filter = EmuFilter::new(sample_rate);
     filter.set_frequency(2000.0); // 2kHz cutoff
     filter.set_resonance(0.3);
-    filter.set_smoothing(0.02);
+    filter.set_smoothing_ms(10.0);
 
     // Generate audio buffer
     let mut audio = vec![0.0f32; samples];
@@ -0,0 +1,220 @@
+//! ADSR envelope generator and modulation routing
+
+use crate::filter::EmuFilterF32;
+use crate::utils::clamp;
+
+/// Envelope stage
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stage {
+    Idle,
+    Attack,
+    Decay,
+    Sustain,
+    Release,
+}
+
+/// ADSR envelope generator
+///
+/// Unlike [`EmuFilterF32`](crate::filter::EmuFilterF32) and [`Oscillator`](crate::oscillator::Oscillator),
+/// this does not store a sample rate: pass it to [`Envelope::next`] each call.
+pub struct Envelope {
+    attack_secs: f32,
+    decay_secs: f32,
+    sustain_level: f32,
+    release_secs: f32,
+
+    stage: Stage,
+    level: f32,
+}
+
+impl Envelope {
+    /// Create a new envelope with reasonable default timings
+    pub fn new() -> Self {
+        Self {
+            attack_secs: 0.01,
+            decay_secs: 0.1,
+            sustain_level: 0.7,
+            release_secs: 0.2,
+            stage: Stage::Idle,
+            level: 0.0,
+        }
+    }
+
+    /// Set attack time in seconds
+    pub fn set_attack(&mut self, seconds: f32) {
+        self.attack_secs = clamp(seconds, 0.0, 60.0);
+    }
+
+    /// Set decay time in seconds
+    pub fn set_decay(&mut self, seconds: f32) {
+        self.decay_secs = clamp(seconds, 0.0, 60.0);
+    }
+
+    /// Set sustain level (0.0 - 1.0)
+    pub fn set_sustain(&mut self, level: f32) {
+        self.sustain_level = clamp(level, 0.0, 1.0);
+    }
+
+    /// Set release time in seconds
+    pub fn set_release(&mut self, seconds: f32) {
+        self.release_secs = clamp(seconds, 0.0, 60.0);
+    }
+
+    /// Start a new note, beginning the attack stage from the current level
+    pub fn gate_on(&mut self) {
+        self.stage = Stage::Attack;
+    }
+
+    /// End the current note, ramping to zero from the current level
+    pub fn gate_off(&mut self) {
+        if self.stage != Stage::Idle {
+            self.stage = Stage::Release;
+        }
+    }
+
+    /// Per-sample increment for ramping from 0.0 to 1.0 over `time_secs`
+    fn increment(&self, time_secs: f32, sample_rate: f32) -> f32 {
+        if time_secs <= 0.0 {
+            1.0
+        } else {
+            1.0 / (time_secs * sample_rate)
+        }
+    }
+
+    /// Generate the next envelope sample
+    pub fn next(&mut self, sample_rate: f32) -> f32 {
+        match self.stage {
+            Stage::Idle => {
+                self.level = 0.0;
+            }
+            Stage::Attack => {
+                self.level += self.increment(self.attack_secs, sample_rate);
+                if self.level >= 1.0 {
+                    self.level = 1.0;
+                    self.stage = Stage::Decay;
+                }
+            }
+            Stage::Decay => {
+                let step = self.increment(self.decay_secs, sample_rate) * (1.0 - self.sustain_level);
+                self.level -= step;
+                if self.level <= self.sustain_level {
+                    self.level = self.sustain_level;
+                    self.stage = Stage::Sustain;
+                }
+            }
+            Stage::Sustain => {
+                self.level = self.sustain_level;
+            }
+            Stage::Release => {
+                self.level -= self.increment(self.release_secs, sample_rate);
+                if self.level <= 0.0 {
+                    self.level = 0.0;
+                    self.stage = Stage::Idle;
+                }
+            }
+        }
+
+        self.level
+    }
+
+    /// Whether the envelope is still producing non-zero output
+    pub fn is_active(&self) -> bool {
+        self.stage != Stage::Idle
+    }
+
+    /// Scale an oscillator's output buffer by this envelope, advancing one
+    /// step per sample
+    pub fn modulate_amplitude(&mut self, buffer: &mut [f32], sample_rate: f32) {
+        for sample in buffer.iter_mut() {
+            *sample *= self.next(sample_rate);
+        }
+    }
+
+    /// Add this envelope's current level (scaled by `amount` Hz) on top of a
+    /// filter's base cutoff, without advancing the envelope
+    pub fn modulate_cutoff(&self, filter: &mut EmuFilterF32, base_freq: f32, amount: f32) {
+        filter.set_frequency(base_freq + self.level * amount);
+    }
+}
+
+impl Default for Envelope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_idle_by_default() {
+        let mut env = Envelope::new();
+        assert_eq!(env.next(44100.0), 0.0);
+        assert!(!env.is_active());
+    }
+
+    #[test]
+    fn test_envelope_attack_reaches_peak() {
+        let mut env = Envelope::new();
+        env.set_attack(0.001);
+        env.gate_on();
+
+        let mut peak = 0.0f32;
+        for _ in 0..200 {
+            peak = peak.max(env.next(44100.0));
+        }
+
+        assert!((peak - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_envelope_release_returns_to_idle() {
+        let mut env = Envelope::new();
+        env.set_attack(0.0001);
+        env.set_decay(0.0001);
+        env.set_sustain(0.5);
+        env.set_release(0.0001);
+        env.gate_on();
+
+        for _ in 0..100 {
+            env.next(44100.0);
+        }
+        env.gate_off();
+        for _ in 0..1000 {
+            env.next(44100.0);
+        }
+
+        assert_eq!(env.next(44100.0), 0.0);
+        assert!(!env.is_active());
+    }
+
+    #[test]
+    fn test_modulate_amplitude_scales_buffer() {
+        let mut env = Envelope::new();
+        env.set_attack(1000.0); // effectively never reaches peak in this test
+        env.gate_on();
+
+        let mut buffer = vec![1.0f32; 8];
+        env.modulate_amplitude(&mut buffer, 44100.0);
+
+        // Early in a long attack the envelope level is small, so the buffer
+        // should be attenuated rather than passed through unchanged
+        assert!(buffer[7] < 1.0);
+    }
+
+    #[test]
+    fn test_modulate_cutoff_adds_to_base_frequency() {
+        let mut env = Envelope::new();
+        env.set_attack(0.0001);
+        env.gate_on();
+        for _ in 0..200 {
+            env.next(44100.0);
+        }
+
+        let mut filter = EmuFilterF32::new(44100.0);
+        env.modulate_cutoff(&mut filter, 200.0, 2000.0);
+
+        assert!(filter.target_frequency() > 200.0);
+    }
+}
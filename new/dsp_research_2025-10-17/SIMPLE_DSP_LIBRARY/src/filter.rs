@@ -1,168 +1,216 @@
 //! Simple EMU-style filter implementation
 
-use crate::utils::{clamp, smooth};
-
-/// Simple EMU-style filter
-pub struct EmuFilter {
-    sample_rate: f32,
-    frequency: f32,
-    resonance: f32,
-    target_frequency: f32,
-    target_resonance: f32,
-
-    // Biquad coefficients
-    b0: f32,
-    b1: f32,
-    b2: f32,
-    a1: f32,
-    a2: f32,
-
-    // Filter state
-    x1: f32,
-    x2: f32,
-    y1: f32,
-    y2: f32,
-
-    // Smoothing
-    freq_smoothing: f32,
-    res_smoothing: f32,
+use crate::utils::{clamp, Float, Smoother};
+
+/// Filter response type selected via [`EmuFilter::set_mode`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterMode {
+    /// Low frequencies pass, high frequencies are attenuated
+    Lowpass,
+    /// High frequencies pass, low frequencies are attenuated
+    Highpass,
+    /// Only frequencies around the cutoff pass
+    Bandpass,
+    /// Frequencies around the cutoff are attenuated
+    Notch,
 }
 
-impl EmuFilter {
+/// Minimum change in frequency (Hz) or resonance (0-1) that triggers a
+/// coefficient recompute, so a smoother ticking by a tiny fraction every
+/// sample doesn't pay for a `tan()` call on every sample too.
+const FREQ_RECOMPUTE_THRESHOLD: f32 = 0.5;
+const RES_RECOMPUTE_THRESHOLD: f32 = 0.001;
+
+/// Simple EMU-style filter, generic over the sample type `T` (`f32` or
+/// `f64`). Most users want [`EmuFilterF32`].
+pub struct EmuFilter<T: Float> {
+    sample_rate: T,
+    mode: FilterMode,
+
+    // Sample-accurate parameter smoothing
+    freq_smoother: Smoother<T>,
+    res_smoother: Smoother<T>,
+    smoothing_ms: T,
+
+    // Frequency/resonance the coefficients below were last computed for
+    coeff_frequency: T,
+    coeff_resonance: T,
+
+    // TPT state-variable coefficients
+    g: T,
+    k: T,
+    a1: T,
+    a2: T,
+    a3: T,
+
+    // Integrator states
+    v1: T,
+    v2: T,
+}
+
+impl<T: Float> EmuFilter<T> {
     /// Create new filter
-    pub fn new(sample_rate: f32) -> Self {
+    pub fn new(sample_rate: T) -> Self {
+        let frequency = T::from_f32(1000.0);
+        let resonance = T::from_f32(0.5);
+
         let mut filter = Self {
             sample_rate,
-            frequency: 1000.0,
-            resonance: 0.5,
-            target_frequency: 1000.0,
-            target_resonance: 0.5,
-            b0: 0.0,
-            b1: 0.0,
-            b2: 0.0,
-            a1: 0.0,
-            a2: 0.0,
-            x1: 0.0,
-            x2: 0.0,
-            y1: 0.0,
-            y2: 0.0,
-            freq_smoothing: 0.001,
-            res_smoothing: 0.001,
+            mode: FilterMode::Lowpass,
+            freq_smoother: Smoother::new(frequency),
+            res_smoother: Smoother::new(resonance),
+            smoothing_ms: T::from_f32(5.0),
+            coeff_frequency: frequency,
+            coeff_resonance: resonance,
+            g: T::from_f32(0.0),
+            k: T::from_f32(0.0),
+            a1: T::from_f32(0.0),
+            a2: T::from_f32(0.0),
+            a3: T::from_f32(0.0),
+            v1: T::from_f32(0.0),
+            v2: T::from_f32(0.0),
         };
         filter.update_coefficients();
         filter
     }
 
     /// Set frequency (20Hz - 20kHz)
-    pub fn set_frequency(&mut self, freq: f32) {
-        self.target_frequency = clamp(freq, 20.0, 20000.0);
+    pub fn set_frequency(&mut self, freq: T) {
+        let target = clamp(freq, T::from_f32(20.0), T::from_f32(20000.0));
+        self.freq_smoother
+            .set_target(target, self.smoothing_ms, self.sample_rate);
     }
 
     /// Set resonance (0.0 - 1.0)
-    pub fn set_resonance(&mut self, res: f32) {
-        self.target_resonance = clamp(res, 0.0, 0.99);
+    pub fn set_resonance(&mut self, res: T) {
+        let target = clamp(res, T::from_f32(0.0), T::from_f32(0.99));
+        self.res_smoother
+            .set_target(target, self.smoothing_ms, self.sample_rate);
     }
 
-    /// Set smoothing factor (0.0 - 1.0)
-    pub fn set_smoothing(&mut self, smoothing: f32) {
-        let s = clamp(smoothing, 0.0, 1.0);
-        self.freq_smoothing = s;
-        self.res_smoothing = s;
+    /// Select the filter response type
+    pub fn set_mode(&mut self, mode: FilterMode) {
+        self.mode = mode;
     }
 
-    /// Process audio buffer
-    pub fn process(&mut self, buffer: &mut [f32]) {
-        // Smooth parameter changes
-        self.frequency = smooth(self.frequency, self.target_frequency, self.freq_smoothing);
-        self.resonance = smooth(self.resonance, self.target_resonance, self.res_smoothing);
-
-        // Update coefficients if parameters changed significantly
-        if (self.frequency - self.target_frequency).abs() > 1.0
-            || (self.resonance - self.target_resonance).abs() > 0.001
-        {
-            self.update_coefficients();
-        }
+    /// Set the ramp time, in milliseconds, over which frequency and
+    /// resonance changes are smoothed
+    pub fn set_smoothing_ms(&mut self, ms: T) {
+        self.smoothing_ms = clamp(ms, T::from_f32(0.0), T::from_f32(1000.0));
+    }
 
-        // Process samples
+    /// Process audio buffer
+    pub fn process(&mut self, buffer: &mut [T]) {
         for sample in buffer.iter_mut() {
-            let input = *sample;
-
-            // Biquad difference equation
-            let output = self.b0 * input + self.b1 * self.x1 + self.b2 * self.x2
-                - self.a1 * self.y1
-                - self.a2 * self.y2;
-
-            // Update delay lines
-            self.x2 = self.x1;
-            self.x1 = input;
-            self.y2 = self.y1;
-            self.y1 = output;
+            let frequency = self.freq_smoother.tick();
+            let resonance = self.res_smoother.tick();
+
+            if (frequency - self.coeff_frequency).abs() > T::from_f32(FREQ_RECOMPUTE_THRESHOLD)
+                || (resonance - self.coeff_resonance).abs() > T::from_f32(RES_RECOMPUTE_THRESHOLD)
+            {
+                self.coeff_frequency = frequency;
+                self.coeff_resonance = resonance;
+                self.update_coefficients();
+            }
+
+            *sample = self.process_sample(*sample);
+        }
+    }
 
-            *sample = output;
+    /// Process a single sample through the state-variable filter
+    #[inline]
+    fn process_sample(&mut self, input: T) -> T {
+        // Chamberlin/TPT state-variable filter (zero-delay feedback).
+        // `v1`/`v2` are the integrator states; `bp`/`lp` are this sample's
+        // bandpass/lowpass outputs, solved without a delay-free loop.
+        let v3 = input - self.v2;
+        let bp = self.a1 * self.v1 + self.a2 * v3;
+        let lp = self.v2 + self.a2 * self.v1 + self.a3 * v3;
+        self.v1 = T::from_f32(2.0) * bp - self.v1;
+        self.v2 = T::from_f32(2.0) * lp - self.v2;
+
+        let hp = input - self.k * bp - lp;
+
+        match self.mode {
+            FilterMode::Lowpass => lp,
+            FilterMode::Highpass => hp,
+            FilterMode::Bandpass => bp,
+            FilterMode::Notch => hp + lp,
         }
     }
 
-    /// Update biquad coefficients
+    /// Update state-variable coefficients from `coeff_frequency`/`coeff_resonance`
     fn update_coefficients(&mut self) {
-        let omega = 2.0 * std::f32::consts::PI * self.frequency / self.sample_rate;
-        let sin_omega = omega.sin();
-        let cos_omega = omega.cos();
-        let alpha = sin_omega / (2.0 * (1.0 - self.resonance));
+        let omega = T::pi() * self.coeff_frequency / self.sample_rate;
+        self.g = omega.tan();
 
-        // Lowpass biquad coefficients
-        let a0 = 1.0 + alpha;
+        // Q factor from resonance, mapped so higher resonance narrows the band
+        let q =
+            T::from_f32(1.0) / (T::from_f32(2.0) * (T::from_f32(1.0) - self.coeff_resonance));
+        self.k = T::from_f32(1.0) / q;
 
-        self.b0 = (1.0 - cos_omega) / (2.0 * a0);
-        self.b1 = (1.0 - cos_omega) / a0;
-        self.b2 = (1.0 - cos_omega) / (2.0 * a0);
-        self.a1 = -2.0 * cos_omega / a0;
-        self.a2 = (1.0 - alpha) / a0;
+        self.a1 = T::from_f32(1.0) / (T::from_f32(1.0) + self.g * (self.g + self.k));
+        self.a2 = self.g * self.a1;
+        self.a3 = self.g * self.a2;
     }
 
     /// Reset filter state
     pub fn reset(&mut self) {
-        self.x1 = 0.0;
-        self.x2 = 0.0;
-        self.y1 = 0.0;
-        self.y2 = 0.0;
-        self.frequency = self.target_frequency;
-        self.resonance = self.target_resonance;
+        self.v1 = T::from_f32(0.0);
+        self.v2 = T::from_f32(0.0);
+        self.freq_smoother = Smoother::new(self.freq_smoother.target());
+        self.res_smoother = Smoother::new(self.res_smoother.target());
+        self.coeff_frequency = self.freq_smoother.target();
+        self.coeff_resonance = self.res_smoother.target();
         self.update_coefficients();
     }
+
+    /// Get the target cutoff frequency (not yet smoothed)
+    pub fn target_frequency(&self) -> T {
+        self.freq_smoother.target()
+    }
+
+    /// Get the target resonance (not yet smoothed)
+    pub fn target_resonance(&self) -> T {
+        self.res_smoother.target()
+    }
 }
 
+/// `EmuFilter<f32>`, the precision used throughout the rest of this crate
+pub type EmuFilterF32 = EmuFilter<f32>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_filter_creation() {
-        let filter = EmuFilter::new(44100.0);
-        assert_eq!(filter.frequency, 1000.0);
-        assert_eq!(filter.resonance, 0.5);
+        let filter = EmuFilter::new(44100.0f32);
+        assert_eq!(filter.freq_smoother.current(), 1000.0);
+        assert_eq!(filter.res_smoother.current(), 0.5);
     }
 
     #[test]
     fn test_parameter_setting() {
-        let mut filter = EmuFilter::new(44100.0);
+        let mut filter = EmuFilter::new(44100.0f32);
 
         filter.set_frequency(500.0);
-        assert_eq!(filter.target_frequency, 500.0);
+        assert_eq!(filter.target_frequency(), 500.0);
 
         filter.set_resonance(0.8);
-        assert_eq!(filter.target_resonance, 0.8);
+        assert_eq!(filter.target_resonance(), 0.8);
 
         // Test clamping
         filter.set_frequency(10.0);
-        assert_eq!(filter.target_frequency, 20.0);
+        assert_eq!(filter.target_frequency(), 20.0);
 
         filter.set_resonance(1.5);
-        assert_eq!(filter.target_resonance, 0.99);
+        assert_eq!(filter.target_resonance(), 0.99);
     }
 
     #[test]
     fn test_filter_processing() {
-        let mut filter = EmuFilter::new(44100.0);
+        let mut filter = EmuFilter::new(44100.0f32);
 
         // Create impulse input
         let mut input = vec![0.0f32; 64];
@@ -175,9 +223,32 @@ mod tests {
         assert!(input[0] != 1.0); // Should be attenuated
     }
 
+    #[test]
+    fn test_filter_modes_produce_output() {
+        let modes = [
+            FilterMode::Lowpass,
+            FilterMode::Highpass,
+            FilterMode::Bandpass,
+            FilterMode::Notch,
+        ];
+
+        for mode in modes {
+            let mut filter = EmuFilter::new(44100.0f32);
+            filter.set_mode(mode);
+            filter.set_frequency(1000.0);
+            filter.set_resonance(0.5);
+
+            let mut buffer = vec![0.0f32; 64];
+            buffer[0] = 1.0;
+            filter.process(&mut buffer);
+
+            assert!(buffer.iter().any(|&x| x != 0.0));
+        }
+    }
+
     #[test]
     fn test_filter_reset() {
-        let mut filter = EmuFilter::new(44100.0);
+        let mut filter = EmuFilter::new(44100.0f32);
 
         // Process some audio to change state
         let mut buffer = vec![0.5f32; 64];
@@ -185,9 +256,61 @@ mod tests {
 
         // Reset should clear state
         filter.reset();
-        assert_eq!(filter.x1, 0.0);
-        assert_eq!(filter.x2, 0.0);
-        assert_eq!(filter.y1, 0.0);
-        assert_eq!(filter.y2, 0.0);
+        assert_eq!(filter.v1, 0.0);
+        assert_eq!(filter.v2, 0.0);
+    }
+
+    #[test]
+    fn test_filter_works_in_f64() {
+        let mut filter: EmuFilter<f64> = EmuFilter::new(44100.0);
+        filter.set_frequency(1000.0);
+        filter.set_resonance(0.7);
+
+        let mut buffer = vec![0.0f64; 64];
+        buffer[0] = 1.0;
+        filter.process(&mut buffer);
+
+        assert!(buffer.iter().any(|&x| x != 0.0));
+    }
+
+    #[test]
+    fn test_frequency_sweep_is_zipper_free_regardless_of_block_size() {
+        // A frequency change smoothed one sample at a time should settle at
+        // (roughly) the same output whether delivered in one big block or
+        // many small ones.
+        let mut one_block = EmuFilter::new(44100.0f32);
+        one_block.set_smoothing_ms(10.0);
+        one_block.set_frequency(5000.0);
+        let mut buffer_a = vec![0.5f32; 1000];
+        one_block.process(&mut buffer_a);
+
+        let mut many_blocks = EmuFilter::new(44100.0f32);
+        many_blocks.set_smoothing_ms(10.0);
+        many_blocks.set_frequency(5000.0);
+        let mut buffer_b = Vec::with_capacity(1000);
+        for _ in 0..1000 {
+            let mut chunk = [0.5f32];
+            many_blocks.process(&mut chunk);
+            buffer_b.push(chunk[0]);
+        }
+
+        for (a, b) in buffer_a.iter().zip(buffer_b.iter()) {
+            assert!((a - b).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_smoothing_ramps_over_configured_time() {
+        let mut filter = EmuFilter::new(1000.0f32); // 1 sample == 1ms
+        filter.set_smoothing_ms(10.0);
+        filter.set_frequency(2000.0);
+
+        let mut buffer = vec![0.0f32; 9];
+        filter.process(&mut buffer);
+        assert!(filter.freq_smoother.current() < 2000.0);
+
+        let mut one_more = [0.0f32];
+        filter.process(&mut one_more);
+        assert_eq!(filter.freq_smoother.current(), 2000.0);
     }
 }
@@ -2,14 +2,21 @@
 //!
 //! Clean, focused DSP with just the essentials.
 
+pub mod envelope;
 pub mod filter;
 pub mod oscillator;
+pub mod trig;
 pub mod utils;
 
-pub use filter::EmuFilter;
+pub use envelope::Envelope;
+pub use filter::{EmuFilter, EmuFilterF32};
 pub use oscillator::Oscillator;
 pub use utils::{lerp, smooth};
 
+/// Real-time audio output via a lock-free ring buffer
+#[cfg(feature = "realtime")]
+pub mod realtime;
+
 /// Library version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
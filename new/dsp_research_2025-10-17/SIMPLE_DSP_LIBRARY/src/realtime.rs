@@ -0,0 +1,221 @@
+//! Real-time audio output backend
+//!
+//! Feature-gated behind `realtime`. Opens the system's default output device
+//! via [`cpal`] and streams generated audio through a lock-free
+//! single-producer/single-consumer ring buffer, so the audio callback never
+//! allocates or locks.
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Number of mono frames generated per block on the producer thread
+const BLOCK_SIZE: usize = 256;
+
+/// Ring buffer capacity, in blocks, before the producer stalls
+const RING_CAPACITY_BLOCKS: usize = 8;
+
+/// Lock-free single-producer/single-consumer ring buffer of interleaved
+/// samples. The producer (this crate's generation thread) calls [`push`](RingBuffer::push);
+/// the consumer (the audio callback) calls [`pop`](RingBuffer::pop).
+struct RingBuffer {
+    data: Box<[UnsafeCell<f32>]>,
+    capacity: usize,
+    head: AtomicUsize,
+    tail: AtomicUsize,
+}
+
+// SAFETY: `push` is only ever called from the producer thread and `pop` only
+// from the consumer thread; the atomic head/tail indices ensure each slot is
+// written before it is read and never written and read concurrently.
+unsafe impl Sync for RingBuffer {}
+
+impl RingBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            data: (0..capacity).map(|_| UnsafeCell::new(0.0)).collect(),
+            capacity,
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.tail
+            .load(Ordering::Acquire)
+            .wrapping_sub(self.head.load(Ordering::Acquire))
+    }
+
+    /// Mono frames the producer has room for, channel-aware: when the
+    /// device is stereo each mono frame needs two slots, so this divides the
+    /// free slot count by `channels` before reporting how many frames there
+    /// is room for.
+    fn space_available(&self, channels: usize) -> usize {
+        (self.capacity - self.len()) / channels.max(1)
+    }
+
+    fn push(&self, value: f32) -> bool {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+        if tail.wrapping_sub(head) >= self.capacity {
+            return false;
+        }
+        unsafe {
+            *self.data[tail % self.capacity].get() = value;
+        }
+        self.tail.store(tail.wrapping_add(1), Ordering::Release);
+        true
+    }
+
+    fn pop(&self) -> Option<f32> {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+        let value = unsafe { *self.data[head % self.capacity].get() };
+        self.head.store(head.wrapping_add(1), Ordering::Release);
+        Some(value)
+    }
+}
+
+/// Anything that can fill a buffer with the next block of audio, e.g.
+/// [`Oscillator`](crate::oscillator::Oscillator) or
+/// [`EmuFilterF32`](crate::filter::EmuFilterF32).
+pub trait AudioSource {
+    /// Fill `buffer` with the next `buffer.len()` mono samples
+    fn process(&mut self, buffer: &mut [f32]);
+}
+
+impl AudioSource for crate::oscillator::Oscillator {
+    fn process(&mut self, buffer: &mut [f32]) {
+        crate::oscillator::Oscillator::process(self, buffer)
+    }
+}
+
+impl AudioSource for crate::filter::EmuFilterF32 {
+    fn process(&mut self, buffer: &mut [f32]) {
+        crate::filter::EmuFilter::process(self, buffer)
+    }
+}
+
+/// Errors [`play`] can return while setting up the output stream
+#[derive(Debug)]
+pub enum RealtimeError {
+    /// Failed to build the output stream
+    BuildStream(cpal::BuildStreamError),
+    /// Failed to start the output stream
+    PlayStream(cpal::PlayStreamError),
+}
+
+impl std::fmt::Display for RealtimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RealtimeError::BuildStream(err) => write!(f, "failed to build stream: {err}"),
+            RealtimeError::PlayStream(err) => write!(f, "failed to play stream: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RealtimeError {}
+
+impl From<cpal::BuildStreamError> for RealtimeError {
+    fn from(err: cpal::BuildStreamError) -> Self {
+        RealtimeError::BuildStream(err)
+    }
+}
+
+impl From<cpal::PlayStreamError> for RealtimeError {
+    fn from(err: cpal::PlayStreamError) -> Self {
+        RealtimeError::PlayStream(err)
+    }
+}
+
+/// Stream audio generated by `source` to the default output device.
+///
+/// Only setup failures are returned. Once the stream is playing this never
+/// returns: errors reported by the running stream are printed to stderr and
+/// generation continues. Generation happens on
+/// the calling thread in blocks of [`BLOCK_SIZE`] mono frames, refusing to
+/// push a block unless the ring buffer has room for `BLOCK_SIZE * channels`
+/// samples, while the real-time audio callback only ever pops already-written
+/// samples out of the ring buffer.
+pub fn play(mut source: impl AudioSource) -> Result<(), RealtimeError> {
+    let host = cpal::default_host();
+    let device = host
+        .default_output_device()
+        .expect("no default output device");
+    let config = device
+        .default_output_config()
+        .expect("no default output device config");
+    let channels = config.channels() as usize;
+
+    let ring = Arc::new(RingBuffer::new(
+        BLOCK_SIZE * RING_CAPACITY_BLOCKS * channels,
+    ));
+    let callback_ring = Arc::clone(&ring);
+
+    let stream = device.build_output_stream(
+        &config.into(),
+        move |output: &mut [f32], _| {
+            for sample in output.iter_mut() {
+                *sample = callback_ring.pop().unwrap_or(0.0);
+            }
+        },
+        |err| eprintln!("audio stream error: {err}"),
+        None,
+    )?;
+    stream.play()?;
+
+    let mut block = vec![0.0f32; BLOCK_SIZE];
+    loop {
+        if ring.space_available(channels) < block.len() {
+            std::thread::yield_now();
+            continue;
+        }
+
+        source.process(&mut block);
+        for &sample in &block {
+            for _ in 0..channels {
+                ring.push(sample);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer_push_pop_roundtrip() {
+        let ring = RingBuffer::new(4);
+        assert!(ring.push(1.0));
+        assert!(ring.push(2.0));
+        assert_eq!(ring.pop(), Some(1.0));
+        assert_eq!(ring.pop(), Some(2.0));
+        assert_eq!(ring.pop(), None);
+    }
+
+    #[test]
+    fn test_ring_buffer_refuses_to_overfill() {
+        let ring = RingBuffer::new(2);
+        assert!(ring.push(1.0));
+        assert!(ring.push(2.0));
+        assert!(!ring.push(3.0)); // full
+    }
+
+    #[test]
+    fn test_space_available_is_channel_aware() {
+        let ring = RingBuffer::new(16);
+        // Empty buffer, stereo: 16 free slots / 2 channels = 8 mono frames
+        assert_eq!(ring.space_available(2), 8);
+
+        for _ in 0..4 {
+            assert!(ring.push(0.0));
+        }
+        // 12 free slots / 2 channels = 6 mono frames
+        assert_eq!(ring.space_available(2), 6);
+    }
+}
@@ -0,0 +1,92 @@
+//! Fast trigonometric approximations
+//!
+//! A 512-entry cosine lookup table (plus one guard entry) so filter
+//! coefficient updates don't pay for a `sin`/`cos` call on every recompute.
+
+use std::f32::consts::{PI, TAU};
+use std::sync::OnceLock;
+
+/// Number of entries in the table (power of two)
+const TAB_SIZE: usize = 512;
+
+/// `1.0 / TAU`, used to normalize a phase in radians into `[0, 1)`
+const PHASE_SCALE: f32 = 1.0 / TAU;
+
+static COS_TAB: OnceLock<[f32; TAB_SIZE + 1]> = OnceLock::new();
+
+/// Initialize the cosine lookup table. Idempotent and safe to call more than
+/// once; the table is only ever built the first time.
+pub fn init_cos_tab() {
+    COS_TAB.get_or_init(build_cos_tab);
+}
+
+fn build_cos_tab() -> [f32; TAB_SIZE + 1] {
+    let mut table = [0.0f32; TAB_SIZE + 1];
+    for (i, entry) in table.iter_mut().enumerate() {
+        *entry = (i as f32 / TAB_SIZE as f32 * TAU).cos();
+    }
+    table
+}
+
+fn cos_tab() -> &'static [f32; TAB_SIZE + 1] {
+    COS_TAB.get_or_init(build_cos_tab)
+}
+
+/// Fast cosine approximation via table lookup with linear interpolation
+#[inline]
+pub fn fast_cos(x: f32) -> f32 {
+    let table = cos_tab();
+
+    // Cosine is even
+    let phase = x.abs() * PHASE_SCALE;
+    let normalized = phase.fract();
+    let index_f = normalized * TAB_SIZE as f32;
+    let index = index_f as usize;
+    let frac = index_f - index as f32;
+
+    // The guard entry at `table[TAB_SIZE]` mirrors `table[0]`, so this never
+    // indexes out of bounds even when `index == TAB_SIZE - 1`.
+    table[index] + (table[index + 1] - table[index]) * frac
+}
+
+/// Fast sine approximation, derived from [`fast_cos`]
+#[inline]
+pub fn fast_sin(x: f32) -> f32 {
+    fast_cos(x - PI / 2.0)
+}
+
+/// Fast tangent approximation, derived from [`fast_sin`] and [`fast_cos`].
+/// Used by [`EmuFilter`](crate::filter::EmuFilter)'s `f32` coefficient
+/// updates so retuning the cutoff doesn't pay for a `tan` call.
+#[inline]
+pub fn fast_tan(x: f32) -> f32 {
+    fast_sin(x) / fast_cos(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fast_trig_matches_std() {
+        init_cos_tab();
+
+        for i in 0..1000 {
+            let x = (i as f32 / 1000.0) * 4.0 * PI - 2.0 * PI;
+            assert!((fast_sin(x) - x.sin()).abs() < 0.001);
+            assert!((fast_cos(x) - x.cos()).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn test_fast_tan_matches_std() {
+        init_cos_tab();
+
+        // Stay away from the +/- PI/2 asymptotes, where both the table's
+        // interpolation error and tan's sensitivity blow up.
+        for i in 0..1000 {
+            let x = (i as f32 / 1000.0 - 0.5) * 2.8;
+            assert!((fast_tan(x) - x.tan()).abs() < 0.01);
+        }
+    }
+}
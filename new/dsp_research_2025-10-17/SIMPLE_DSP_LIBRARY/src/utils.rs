@@ -1,28 +1,191 @@
 //! Simple utility functions
 
+use crate::trig::fast_tan;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Minimal floating-point trait bounding the sample types DSP code can run
+/// over (`f32` or `f64`), so the math in this crate can be written once and
+/// shared across precisions instead of being duplicated per type.
+pub trait Float:
+    Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    /// The constant pi, in this type's precision
+    fn pi() -> Self;
+    /// Convert from an `f32` literal, e.g. `T::from_f32(20.0)`
+    fn from_f32(value: f32) -> Self;
+    /// Convert to `f32`, e.g. for display or interop with f32-only code
+    fn to_f32(self) -> f32;
+
+    fn abs(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn tan(self) -> Self;
+    fn log2(self) -> Self;
+    fn log10(self) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn min(self, other: Self) -> Self;
+}
+
+macro_rules! impl_float {
+    ($t:ty, $pi:expr, $tan:expr) => {
+        impl Float for $t {
+            fn pi() -> Self {
+                $pi
+            }
+            fn from_f32(value: f32) -> Self {
+                value as $t
+            }
+            fn to_f32(self) -> f32 {
+                self as f32
+            }
+            fn abs(self) -> Self {
+                <$t>::abs(self)
+            }
+            fn sqrt(self) -> Self {
+                <$t>::sqrt(self)
+            }
+            fn tan(self) -> Self {
+                $tan(self)
+            }
+            fn log2(self) -> Self {
+                <$t>::log2(self)
+            }
+            fn log10(self) -> Self {
+                <$t>::log10(self)
+            }
+            fn powf(self, n: Self) -> Self {
+                <$t>::powf(self, n)
+            }
+            fn max(self, other: Self) -> Self {
+                <$t>::max(self, other)
+            }
+            fn min(self, other: Self) -> Self {
+                <$t>::min(self, other)
+            }
+        }
+    };
+}
+
+// `f32`'s `tan` goes through the fast lookup-table approximation in
+// `trig`, since `EmuFilterF32` is the precision used throughout the rest of
+// the crate and its coefficient updates call `tan` on every retune; `f64`
+// keeps the exact `std` implementation, since the table is only built to
+// `f32` precision.
+impl_float!(f32, std::f32::consts::PI, fast_tan);
+impl_float!(f64, std::f64::consts::PI, <f64>::tan);
+
 /// Linear interpolation
-pub fn lerp(a: f32, b: f32, t: f32) -> f32 {
+pub fn lerp<T: Float>(a: T, b: T, t: T) -> T {
     a + (b - a) * t
 }
 
 /// Simple exponential smoothing
-pub fn smooth(current: f32, target: f32, smoothing: f32) -> f32 {
+pub fn smooth<T: Float>(current: T, target: T, smoothing: T) -> T {
     current + (target - current) * smoothing
 }
 
 /// Clamp value between min and max
-pub fn clamp(value: f32, min: f32, max: f32) -> f32 {
+pub fn clamp<T: Float>(value: T, min: T, max: T) -> T {
     value.max(min).min(max)
 }
 
+/// Convert dB to linear gain
+pub fn db_to_linear<T: Float>(db: T) -> T {
+    T::from_f32(10.0).powf(db / T::from_f32(20.0))
+}
+
+/// Convert linear gain to dB
+pub fn linear_to_db<T: Float>(linear: T) -> T {
+    T::from_f32(20.0) * linear.log10()
+}
+
 /// Convert frequency to MIDI note number
-pub fn freq_to_midi(freq: f32) -> f32 {
-    69.0 + 12.0 * (freq / 440.0).log2()
+pub fn freq_to_midi<T: Float>(freq: T) -> T {
+    T::from_f32(69.0) + T::from_f32(12.0) * (freq / T::from_f32(440.0)).log2()
 }
 
 /// Convert MIDI note number to frequency
-pub fn midi_to_freq(note: f32) -> f32 {
-    440.0 * 2.0_f32.powf((note - 69.0) / 12.0)
+pub fn midi_to_freq<T: Float>(note: T) -> T {
+    T::from_f32(440.0) * T::from_f32(2.0).powf((note - T::from_f32(69.0)) / T::from_f32(12.0))
+}
+
+/// Sample-accurate parameter smoother with a millisecond ramp time.
+///
+/// Unlike [`smooth`], which moves a fixed fraction of the remaining distance
+/// each call (so the ramp time depends on how often it's called), `Smoother`
+/// computes a fixed per-sample `step` from an explicit ramp time, so the
+/// actual wall-clock ramp time is the same regardless of block size.
+pub struct Smoother<T: Float> {
+    current: T,
+    target: T,
+    step: T,
+    // Counts down to 0 rather than comparing `current` to `target` directly,
+    // so accumulated floating-point error from repeated addition can't leave
+    // the smoother forever short of (or past) its target.
+    steps_remaining: u32,
+}
+
+impl<T: Float> Smoother<T> {
+    /// Create a smoother starting at (and targeting) `initial`
+    pub fn new(initial: T) -> Self {
+        Self {
+            current: initial,
+            target: initial,
+            step: T::from_f32(0.0),
+            steps_remaining: 0,
+        }
+    }
+
+    /// Set a new target to ramp toward over `ramp_ms` milliseconds at
+    /// `sample_rate`
+    pub fn set_target(&mut self, target: T, ramp_ms: T, sample_rate: T) {
+        self.target = target;
+        let ramp_samples = (ramp_ms * T::from_f32(0.001) * sample_rate).to_f32();
+        self.steps_remaining = ramp_samples.round().max(0.0) as u32;
+        self.step = if self.steps_remaining > 0 {
+            (self.target - self.current) / T::from_f32(self.steps_remaining as f32)
+        } else {
+            self.target - self.current
+        };
+    }
+
+    /// Current value without advancing
+    pub fn current(&self) -> T {
+        self.current
+    }
+
+    /// Target value
+    pub fn target(&self) -> T {
+        self.target
+    }
+
+    /// Whether the smoother has reached its target
+    pub fn is_settled(&self) -> bool {
+        self.steps_remaining == 0 && self.current == self.target
+    }
+
+    /// Advance one sample toward the target, returning the new current value
+    pub fn tick(&mut self) -> T {
+        if self.steps_remaining == 0 {
+            self.current = self.target;
+            return self.current;
+        }
+
+        self.steps_remaining -= 1;
+        self.current = if self.steps_remaining == 0 {
+            self.target
+        } else {
+            self.current + self.step
+        };
+
+        self.current
+    }
 }
 
 #[cfg(test)]
@@ -55,4 +218,48 @@ mod tests {
         assert!((midi_to_freq(69.0) - 440.0).abs() < 0.001);
         assert!((freq_to_midi(440.0) - 69.0).abs() < 0.001);
     }
+
+    #[test]
+    fn test_db_conversion() {
+        assert!((db_to_linear(0.0) - 1.0).abs() < 0.001);
+        assert!(linear_to_db(1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_float_trait_works_for_f64() {
+        assert!((midi_to_freq(69.0_f64) - 440.0).abs() < 0.001);
+        assert!((db_to_linear(0.0_f64) - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_smoother_reaches_target_in_ramp_time() {
+        let mut smoother = Smoother::new(0.0);
+        // 1ms ramp at 1000Hz sample rate is exactly 1 sample
+        smoother.set_target(1.0, 1.0, 1000.0);
+
+        assert_eq!(smoother.tick(), 1.0);
+        assert!(smoother.is_settled());
+    }
+
+    #[test]
+    fn test_smoother_steps_evenly_over_many_samples() {
+        let mut smoother = Smoother::new(0.0);
+        smoother.set_target(10.0, 10.0, 1000.0); // 10ms at 1kHz = 10 samples
+
+        for i in 0..9 {
+            let value = smoother.tick();
+            assert!((value - (i + 1) as f64).abs() < 1e-9);
+            assert!(!smoother.is_settled());
+        }
+        assert_eq!(smoother.tick(), 10.0);
+        assert!(smoother.is_settled());
+    }
+
+    #[test]
+    fn test_smoother_snaps_instantly_with_zero_ramp() {
+        let mut smoother = Smoother::new(0.0);
+        smoother.set_target(5.0, 0.0, 44100.0);
+
+        assert_eq!(smoother.tick(), 5.0);
+    }
 }